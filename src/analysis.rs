@@ -0,0 +1,120 @@
+//! Per-pin current analysis for the 12VHPWR connector.
+//!
+//! A single pin reading above some fixed ampere value isn't actually the
+//! dangerous case on its own — the connector's real failure mode is current
+//! *imbalance*, where one or two pins carry most of the load while the
+//! others idle. This module turns a raw set of six pin currents into that
+//! signal.
+
+/// Configurable thresholds used to flag dangerous per-pin current readings.
+#[derive(Debug, Clone, Copy)]
+pub struct AlertThresholds {
+    /// Fraction above the mean current at which a pin is flagged as
+    /// carrying a disproportionate share of the load.
+    pub imbalance_fraction: f32,
+    /// Per-pin current, in amperes, at or above which a pin is flagged regardless
+    /// of how balanced the load is.
+    pub max_current_amps: f32,
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self {
+            imbalance_fraction: 0.5,
+            max_current_amps: 9.0,
+        }
+    }
+}
+
+/// Derived view over a single sample's six pin currents.
+#[derive(Debug, Clone, Copy)]
+pub struct PowerAnalysis {
+    pub mean_current_amps: f32,
+    pub max_deviation_amps: f32,
+    pub max_deviation_pin: usize,
+    pub pin_alerts: [bool; 6],
+    pub imbalance_alert: bool,
+}
+
+impl PowerAnalysis {
+    /// Compute mean current, per-pin deviation, and threshold alerts for one
+    /// sample of six pin currents.
+    pub fn compute(currents: &[f32; 6], thresholds: &AlertThresholds) -> Self {
+        let mean_current_amps = currents.iter().sum::<f32>() / currents.len() as f32;
+
+        let mut max_deviation_amps = 0.0f32;
+        let mut max_deviation_pin = 0usize;
+        let mut pin_alerts = [false; 6];
+
+        for (i, &current) in currents.iter().enumerate() {
+            let deviation = current - mean_current_amps;
+            if deviation > max_deviation_amps {
+                max_deviation_amps = deviation;
+                max_deviation_pin = i;
+            }
+
+            let imbalanced = mean_current_amps > 0.0
+                && current > mean_current_amps * (1.0 + thresholds.imbalance_fraction);
+            let over_limit = current >= thresholds.max_current_amps;
+            pin_alerts[i] = imbalanced || over_limit;
+        }
+
+        Self {
+            mean_current_amps,
+            max_deviation_amps,
+            max_deviation_pin,
+            pin_alerts,
+            imbalance_alert: pin_alerts.iter().any(|&alerted| alerted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_load_raises_no_alerts() {
+        let currents = [5.0, 5.0, 5.0, 5.0, 5.0, 5.0];
+        let analysis = PowerAnalysis::compute(&currents, &AlertThresholds::default());
+
+        assert_eq!(analysis.mean_current_amps, 5.0);
+        assert_eq!(analysis.pin_alerts, [false; 6]);
+        assert!(!analysis.imbalance_alert);
+    }
+
+    #[test]
+    fn one_pin_over_the_imbalance_fraction_is_flagged() {
+        // Mean is 3.0A; pin 0 at 5.0A is 66% above the mean, past the
+        // default 50% imbalance fraction.
+        let currents = [5.0, 2.6, 2.6, 2.6, 2.6, 2.6];
+        let analysis = PowerAnalysis::compute(&currents, &AlertThresholds::default());
+
+        assert!(analysis.imbalance_alert);
+        assert!(analysis.pin_alerts[0]);
+        assert_eq!(analysis.max_deviation_pin, 0);
+        assert!(analysis.pin_alerts[1..].iter().all(|&a| !a));
+    }
+
+    #[test]
+    fn pin_over_absolute_limit_is_flagged_even_when_balanced() {
+        let thresholds = AlertThresholds {
+            imbalance_fraction: 10.0, // effectively disable the imbalance check
+            max_current_amps: 9.0,
+        };
+        let currents = [9.5, 9.5, 9.5, 9.5, 9.5, 9.5];
+        let analysis = PowerAnalysis::compute(&currents, &thresholds);
+
+        assert!(analysis.imbalance_alert);
+        assert_eq!(analysis.pin_alerts, [true; 6]);
+    }
+
+    #[test]
+    fn all_zero_currents_do_not_flag_imbalance() {
+        let currents = [0.0; 6];
+        let analysis = PowerAnalysis::compute(&currents, &AlertThresholds::default());
+
+        assert!(!analysis.imbalance_alert);
+        assert_eq!(analysis.pin_alerts, [false; 6]);
+    }
+}
@@ -1,10 +1,131 @@
+mod analysis;
 mod monitor;
+mod output;
+mod sampling;
 
+use analysis::AlertThresholds;
 use monitor::AstralPowerMonitor;
+use output::{CsvFormatter, FileSink, Formatter, JsonLinesFormatter, PrettyFormatter, Sink, StdoutSink};
+use sampling::sample_all;
+
+/// Output format selected via `--format`.
+enum OutputFormat {
+    Pretty,
+    Json,
+    Csv,
+}
+
+struct Config {
+    thresholds: AlertThresholds,
+    format: OutputFormat,
+    output_path: Option<String>,
+    interval: std::time::Duration,
+    power_tolerance_watts: f32,
+}
+
+/// Default tolerance for the IT8915-vs-NVAPI power cross-check. Chosen as an
+/// absolute margin in watts, generous enough to absorb normal measurement skew
+/// between the two independent sources without masking a genuinely
+/// miscalibrated block.
+const DEFAULT_POWER_TOLERANCE_WATTS: f32 = 15.0;
+
+/// Parse CLI flags:
+/// * `--imbalance-fraction <f32>` / `--max-current <f32>` - alert thresholds
+/// * `--format pretty|json|csv` - output format (default: pretty)
+/// * `--output <path>` - write to a file instead of stdout
+/// * `--interval <seconds>` - sample interval in seconds (default: 1)
+/// * `--power-tolerance <watts>` - allowed IT8915-vs-NVAPI power divergence
+fn parse_config() -> Config {
+    let mut thresholds = AlertThresholds::default();
+    let mut format = OutputFormat::Pretty;
+    let mut output_path = None;
+    let mut interval = std::time::Duration::from_secs(1);
+    let mut power_tolerance_watts = DEFAULT_POWER_TOLERANCE_WATTS;
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--imbalance-fraction" => {
+                if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                    thresholds.imbalance_fraction = value;
+                }
+            }
+            "--max-current" => {
+                if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                    thresholds.max_current_amps = value;
+                }
+            }
+            "--format" => {
+                if let Some(value) = args.next() {
+                    format = match value.as_str() {
+                        "json" => OutputFormat::Json,
+                        "csv" => OutputFormat::Csv,
+                        _ => OutputFormat::Pretty,
+                    };
+                }
+            }
+            "--output" => {
+                output_path = args.next();
+            }
+            "--interval" => {
+                if let Some(value) = args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    if value.is_finite() && value > 0.0 {
+                        interval = std::time::Duration::from_secs_f64(value);
+                    }
+                }
+            }
+            "--power-tolerance" => {
+                if let Some(value) = args.next().and_then(|v| v.parse().ok()) {
+                    power_tolerance_watts = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Config {
+        thresholds,
+        format,
+        output_path,
+        interval,
+        power_tolerance_watts,
+    }
+}
 
 fn main() {
-    println!("NVIDIA RTX Astral Pin Power Monitor");
-    println!("=========================================\n");
+    let config = parse_config();
+
+    if matches!(config.format, OutputFormat::Pretty) && config.output_path.is_some() {
+        eprintln!(
+            "Warning: --format pretty writes ANSI escape codes; use --format json or --format csv for a readable log file"
+        );
+    }
+
+    let (mut sink, file_had_content): (Box<dyn Sink>, bool) = match &config.output_path {
+        Some(path) => match FileSink::create(path) {
+            Ok((sink, had_content)) => (Box::new(sink), had_content),
+            Err(e) => {
+                eprintln!("Error opening output file {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => (Box::new(StdoutSink), false),
+    };
+
+    let mut formatter: Box<dyn Formatter> = match config.format {
+        OutputFormat::Pretty => Box::new(PrettyFormatter::new(
+            config.thresholds,
+            config.power_tolerance_watts,
+        )),
+        OutputFormat::Json => Box::new(JsonLinesFormatter::new(config.power_tolerance_watts)),
+        OutputFormat::Csv => Box::new(CsvFormatter::new(file_had_content, config.power_tolerance_watts)),
+    };
+
+    if matches!(config.format, OutputFormat::Pretty) {
+        println!("NVIDIA RTX Astral Pin Power Monitor");
+        println!("=========================================\n");
+    }
 
     // Initialize NVAPI
     let monitor = match AstralPowerMonitor::new() {
@@ -19,59 +140,27 @@ fn main() {
         }
     };
 
-    println!("Found {} NVIDIA GPU(s)\n", monitor.gpu_count());
-
-    // Continuous monitoring loop
-    let gpu_index = 0;
-    let mut voltages = [0.0f32; 6];
-    let mut currents = [0.0f32; 6];
-    let mut first_iteration = true;
+    if matches!(config.format, OutputFormat::Pretty) {
+        println!("Found {} NVIDIA GPU(s)\n", monitor.gpu_count());
+    }
 
+    // Continuous monitoring loop: sample every enumerated GPU each tick,
+    // then route the snapshot through the configured formatter and sink.
     loop {
-        match monitor.get_power_status(gpu_index, &mut voltages, &mut currents) {
-            Ok(()) => {
-                use std::io::Write;
-
-                if !first_iteration {
-                    // Move cursor up 8 lines to overwrite previous data
-                    print!("\x1b[8A");
-                } else {
-                    println!("GPU {} Power Rail Status:", gpu_index);
-                    println!("==========================");
-                    first_iteration = false;
-                }
-
-                let mut total_power = 0.0f32;
-                for i in 0..6 {
-                    let power = voltages[i] * currents[i];
-                    total_power += power;
-
-                    // When do they start melting?
-                    let current_color = if currents[i] >= 9.0 {
-                        "\x1b[91m" // Bright red
-                    } else if currents[i] >= 6.0 {
-                        "\x1b[93m" // Bright yellow
-                    } else {
-                        "\x1b[92m" // Bright green
-                    };
-
-                    print!("  Pin {}: {:.3}V Ã— {}{:.2}A{} = {:.2}W",
-                           i + 1, voltages[i], current_color, currents[i], "\x1b[0m", power);
-                    println!("\x1b[K"); // Clear to end of line
-                }
+        let snapshot = sample_all(&monitor, &config.thresholds);
 
-                println!("\x1b[K"); // Clear blank line
-                print!("  Total Power: {:.2}W", total_power);
-                println!("\x1b[K"); // Clear to end of line
+        for (gpu_index, error) in &snapshot.failures {
+            eprintln!("GPU {}: {}", gpu_index, error);
+        }
 
-                std::io::stdout().flush().unwrap();
-            }
-            Err(e) => {
-                eprintln!("\nError: {}", e);
-                break;
-            }
+        if snapshot.gpus.is_empty() {
+            eprintln!("\nError: failed to read power status from any GPU");
+            break;
         }
 
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        let text = formatter.format(&snapshot);
+        sink.write(&text);
+
+        std::thread::sleep(config.interval);
     }
 }
@@ -1,9 +1,16 @@
+use std::fmt;
 use std::mem;
+use std::thread;
+use std::time::Duration;
 
 use nvapi_sys::gpu::NvAPI_EnumPhysicalGPUs;
+use nvapi_sys::gpu::power::{
+    NvAPI_GPU_GetPowerStatus, NvAPI_GPU_GetVoltageStatus, NV_GPU_POWER_STATUS,
+    NV_GPU_POWER_STATUS_VER, NV_GPU_VOLTAGE_STATUS, NV_GPU_VOLTAGE_STATUS_VER,
+};
 use nvapi_sys::handles::NvPhysicalGpuHandle;
 use nvapi_sys::i2c::{NV_I2C_INFO_VER3, NVAPI_I2C_SPEED_100KHZ};
-use nvapi_sys::i2c::private::{NV_I2C_INFO_EX_V3, NvAPI_I2CReadEx};
+use nvapi_sys::i2c::private::{NV_I2C_INFO_EX_V3, NvAPI_I2CReadEx, NvAPI_I2CWriteEx};
 use nvapi_sys::status::NVAPI_OK;
 use nvapi_sys::types::NVAPI_MAX_PHYSICAL_GPUS;
 
@@ -12,10 +19,149 @@ const IT8915_I2C_ADDRESS: u8 = 0x56; // I2C device address
 const IT8915_POWER_REG_START: u8 = 0x80; // Starting register for power readings
 const IT8915_POWER_DATA_SIZE: usize = 24; // 24 bytes of power data
 
+// Retry policy for transient I2C bus conditions (BUS_BUSY / TIMEOUT)
+const I2C_MAX_ATTEMPTS: u32 = 5;
+const I2C_INITIAL_BACKOFF: Duration = Duration::from_millis(2);
+
+/// Decoded `i2c_status` out-parameter from `NvAPI_I2CReadEx`/`NvAPI_I2CWriteEx`.
+///
+/// This encoding isn't documented by NVIDIA; the values below were inferred
+/// from observed behavior on boards where the IT8915 shares the GPU's I2C
+/// bus with the display/USB-C controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cBusStatus {
+    /// The transaction completed normally.
+    Okay,
+    /// The device never acknowledged its address — a permanent/fatal
+    /// condition (e.g. no IT8915 at 0x56 on this board).
+    NoAck,
+    /// The transaction timed out. Transient; worth retrying.
+    Timeout,
+    /// Another master held the bus. Transient; worth retrying.
+    BusBusy,
+    /// An unrecognized status word.
+    Other(u32),
+}
+
+impl I2cBusStatus {
+    fn from_raw(raw: u32) -> Self {
+        match raw {
+            0 => I2cBusStatus::Okay,
+            1 => I2cBusStatus::NoAck,
+            2 => I2cBusStatus::Timeout,
+            3 => I2cBusStatus::BusBusy,
+            other => I2cBusStatus::Other(other),
+        }
+    }
+
+    #[cfg(test)]
+    fn is_transient(self) -> bool {
+        matches!(self, I2cBusStatus::Timeout | I2cBusStatus::BusBusy)
+    }
+}
+
+impl fmt::Display for I2cBusStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            I2cBusStatus::Okay => write!(f, "okay"),
+            I2cBusStatus::NoAck => write!(f, "no ack"),
+            I2cBusStatus::Timeout => write!(f, "timeout"),
+            I2cBusStatus::BusBusy => write!(f, "bus busy"),
+            I2cBusStatus::Other(code) => write!(f, "unknown status 0x{:x}", code),
+        }
+    }
+}
+
+/// Structured error for I2C transactions against the IT8915, as distinct
+/// from a generic NVAPI failure. Kept structured (rather than stringified)
+/// all the way out to callers so e.g. a permanent `NoAck` ("no such
+/// sensor") can be told apart from a transient `RetriesExhausted(BusBusy)`
+/// ("bus temporarily contended").
+#[derive(Debug, Clone)]
+pub enum MonitorError {
+    /// NVAPI itself returned a non-OK status for the call.
+    Nvapi(String),
+    /// The device never acknowledged — no IT8915 at the expected address.
+    NoAck,
+    /// Bus stayed busy/timed out past the retry budget.
+    RetriesExhausted(I2cBusStatus),
+    /// The bus status word didn't match any known code.
+    UnknownBusStatus(u32),
+    /// The caller passed a GPU index outside `0..gpu_count()`.
+    InvalidGpuIndex { index: i32, max: usize },
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorError::Nvapi(msg) => write!(f, "{}", msg),
+            MonitorError::NoAck => write!(
+                f,
+                "I2C device did not acknowledge (no IT8915 at 0x{:02x}?)",
+                IT8915_I2C_ADDRESS
+            ),
+            MonitorError::RetriesExhausted(status) => {
+                write!(f, "I2C bus still reporting {} after {} attempts", status, I2C_MAX_ATTEMPTS)
+            }
+            MonitorError::UnknownBusStatus(code) => {
+                write!(f, "I2C transaction returned unrecognized bus status 0x{:x}", code)
+            }
+            MonitorError::InvalidGpuIndex { index, max } => {
+                write!(f, "Invalid GPU index {}. Valid range: 0-{}", index, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {}
+
+/// Which NVAPI I2C entry point an `i2c_transaction` call should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum I2cOp {
+    Read,
+    Write,
+}
+
+impl I2cOp {
+    fn label(self) -> &'static str {
+        match self {
+            I2cOp::Read => "read",
+            I2cOp::Write => "write",
+        }
+    }
+}
+
 pub struct AstralPowerMonitor {
     gpu_handles: Vec<NvPhysicalGpuHandle>,
 }
 
+/// Native GPU power/voltage telemetry as reported directly by NVAPI,
+/// independent of anything read from the IT8915 over I2C.
+#[derive(Debug, Clone, Copy)]
+pub struct NativePower {
+    pub power_watts: f32,
+    pub voltage_volts: f32,
+}
+
+/// The IT8915-derived per-pin power total alongside NVAPI's own telemetry,
+/// so a miscalibrated or misread I2C block (wrong endianness, wrong rail
+/// ordering) can be caught by comparing two independent readings instead
+/// of trusting the I2C total blindly.
+#[derive(Debug, Clone, Copy)]
+pub struct ValidatedPowerStatus {
+    pub pin_power_watts: f32,
+    pub native: NativePower,
+    pub delta_watts: f32,
+}
+
+impl ValidatedPowerStatus {
+    /// Whether the IT8915 total and NVAPI's native reading diverge by more
+    /// than `tolerance_watts`.
+    pub fn diverges_beyond(&self, tolerance_watts: f32) -> bool {
+        self.delta_watts.abs() > tolerance_watts
+    }
+}
+
 impl AstralPowerMonitor {
     /// Initialize NVAPI and enumerate GPUs
     pub fn new() -> Result<Self, String> {
@@ -40,74 +186,159 @@ impl AstralPowerMonitor {
         }
     }
 
-    /// Read raw data from IT8915 power monitoring IC via I2C
+    /// Run a single I2C transaction (read or write) against a register on
+    /// the IT8915, retrying on transient bus conditions (`BUS_BUSY` /
+    /// `TIMEOUT`) with capped exponential backoff. A `NO_ACK` or
+    /// unrecognized status is treated as a hard failure and returned
+    /// immediately. Both `read_i2c_data` and `write_i2c_data` funnel
+    /// through here so the `NV_I2C_INFO_EX_V3` setup, bus-status decoding,
+    /// and `gpu_index` validation only live in one place.
     ///
     /// # Arguments
     /// * `gpu_index` - GPU index (0-based)
     /// * `reg_addr` - IT8915 register address
-    /// * `data` - Buffer to receive data
+    /// * `data` - Buffer to receive (read) or send (write)
+    /// * `op` - Whether to issue an NVAPI read or write command
     ///
     /// # Returns
     /// * `Ok(())` on success
-    /// * `Err(String)` with error message on failure
-    fn read_i2c_data(
+    /// * `Err(MonitorError::InvalidGpuIndex)` if `gpu_index` is out of range
+    /// * `Err(MonitorError)` describing why the transaction failed
+    fn i2c_transaction(
         &self,
         gpu_index: i32,
         reg_addr: u8,
         data: &mut [u8],
-    ) -> Result<(), String> {
+        op: I2cOp,
+    ) -> Result<(), MonitorError> {
+        if gpu_index < 0 || gpu_index >= self.gpu_handles.len() as i32 {
+            return Err(MonitorError::InvalidGpuIndex {
+                index: gpu_index,
+                max: self.gpu_handles.len() - 1,
+            });
+        }
+
         let gpu_handle = self.gpu_handles[gpu_index as usize];
 
-        unsafe {
-            let mut reg_addr_buf = reg_addr;
-
-            // This whole structure is probably wrong, but it works.
-            // The following structure that was reverse-engineered from
-            // ExpanModule.dll seems more correct:
-            /*
-                struct NV_I2C_INFO {
-                    version: u32,              // +0x00 (1002D494): 0x030040 = size 64 | version 3
-                    display_mask: u32,         // +0x04 (1002D498): 0
-                    is_ddc_port: u8,           // +0x08 (1002D49C): 0
-                    i2c_dev_address: u8,       // +0x09 (1002D49D): 0x56
-                    _reserved1: u16,           // +0x0A-0x0B: padding
-                    i2c_reg_address: *mut u8,  // +0x0C (1002D4A0): pointer to register
-                    reg_addr_size: u32,        // +0x10 (1002D4A4): 1
-                    i2c_data: *mut u8,         // +0x14 (1002D4A8): pointer to data buffer
-                    i2c_data_size: u32,        // +0x18 (1002D4AC): 24
-                    port_id: u32,              // +0x1C (1002D4B0): 0xFFFF
-                    i2c_speed_khz: u32,        // +0x20 (1002D4B4): 4
-                    is_port_id_set: u8,        // +0x24 (1002D4B8): 1
-                    _reserved3: u8,            // +0x25
-                    _reserved4: u16,           // +0x26-0x27: padding
-                    _reserved5: u32,           // +0x28 (1002D4BC): 1
-                    _reserved6: [u32; 5],      // +0x2C to +0x3F: padding to 64 bytes
+        let mut backoff = I2C_INITIAL_BACKOFF;
+
+        for attempt in 1..=I2C_MAX_ATTEMPTS {
+            unsafe {
+                let mut reg_addr_buf = reg_addr;
+
+                // This whole structure is probably wrong, but it works.
+                // The following structure that was reverse-engineered from
+                // ExpanModule.dll seems more correct:
+                /*
+                    struct NV_I2C_INFO {
+                        version: u32,              // +0x00 (1002D494): 0x030040 = size 64 | version 3
+                        display_mask: u32,         // +0x04 (1002D498): 0
+                        is_ddc_port: u8,           // +0x08 (1002D49C): 0
+                        i2c_dev_address: u8,       // +0x09 (1002D49D): 0x56
+                        _reserved1: u16,           // +0x0A-0x0B: padding
+                        i2c_reg_address: *mut u8,  // +0x0C (1002D4A0): pointer to register
+                        reg_addr_size: u32,        // +0x10 (1002D4A4): 1
+                        i2c_data: *mut u8,         // +0x14 (1002D4A8): pointer to data buffer
+                        i2c_data_size: u32,        // +0x18 (1002D4AC): 24
+                        port_id: u32,              // +0x1C (1002D4B0): 0xFFFF
+                        i2c_speed_khz: u32,        // +0x20 (1002D4B4): 4
+                        is_port_id_set: u8,        // +0x24 (1002D4B8): 1
+                        _reserved3: u8,            // +0x25
+                        _reserved4: u16,           // +0x26-0x27: padding
+                        _reserved5: u32,           // +0x28 (1002D4BC): 1
+                        _reserved6: [u32; 5],      // +0x2C to +0x3F: padding to 64 bytes
+                    }
+                */
+                let mut i2c_info = NV_I2C_INFO_EX_V3 {
+                    version: NV_I2C_INFO_VER3,
+                    displayMask: 0,
+                    bIsDDCPort: 0,
+                    i2cDevAddress: IT8915_I2C_ADDRESS,
+                    pbI2cRegAddress: &mut reg_addr_buf,
+                    regAddrSize: 1,
+                    pbData: data.as_mut_ptr(),
+                    pbRead: data.len() as u32,
+                    cbSize: 0xFFFF,
+                    i2cSpeedKhz: NVAPI_I2C_SPEED_100KHZ,
+                    portId: 0x01,
+                    bIsPortIdSet: 1
+                };
+
+                let mut i2c_status = 0u32;
+                let status = match op {
+                    I2cOp::Read => NvAPI_I2CReadEx(gpu_handle, &mut i2c_info, &mut i2c_status),
+                    I2cOp::Write => NvAPI_I2CWriteEx(gpu_handle, &mut i2c_info, &mut i2c_status),
+                };
+
+                if status != NVAPI_OK {
+                    return Err(MonitorError::Nvapi(format!(
+                        "I2C {} failed: NVAPI status {:?}",
+                        op.label(),
+                        status
+                    )));
                 }
-            */
-            let mut i2c_info = NV_I2C_INFO_EX_V3 {
-                version: NV_I2C_INFO_VER3,
-                displayMask: 0,
-                bIsDDCPort: 0,
-                i2cDevAddress: IT8915_I2C_ADDRESS,
-                pbI2cRegAddress: &mut reg_addr_buf,
-                regAddrSize: 1,
-                pbData: data.as_mut_ptr(),
-                pbRead: data.len() as u32,
-                cbSize: 0xFFFF,
-                i2cSpeedKhz: NVAPI_I2C_SPEED_100KHZ,
-                portId: 0x01,
-                bIsPortIdSet: 1
-            };
-
-            let mut i2c_status = 0u32;
-            let status = NvAPI_I2CReadEx(gpu_handle, &mut i2c_info, &mut i2c_status);
 
-            if status != NVAPI_OK {
-                return Err(format!("I2C read failed: NVAPI status {:?}", status));
+                let bus_status = I2cBusStatus::from_raw(i2c_status);
+                match bus_status {
+                    I2cBusStatus::Okay => return Ok(()),
+                    I2cBusStatus::NoAck => return Err(MonitorError::NoAck),
+                    I2cBusStatus::Timeout | I2cBusStatus::BusBusy => {
+                        if attempt == I2C_MAX_ATTEMPTS {
+                            return Err(MonitorError::RetriesExhausted(bus_status));
+                        }
+                        thread::sleep(backoff);
+                        backoff *= 2;
+                    }
+                    I2cBusStatus::Other(code) => return Err(MonitorError::UnknownBusStatus(code)),
+                }
             }
         }
 
-        Ok(())
+        unreachable!("loop always returns before exhausting I2C_MAX_ATTEMPTS iterations")
+    }
+
+    /// Read raw data from IT8915 power monitoring IC via I2C
+    ///
+    /// # Arguments
+    /// * `gpu_index` - GPU index (0-based)
+    /// * `reg_addr` - IT8915 register address
+    /// * `data` - Buffer to receive data
+    ///
+    /// # Returns
+    /// * `Ok(())` on success
+    /// * `Err(MonitorError)` describing why the transaction failed
+    fn read_i2c_data(
+        &self,
+        gpu_index: i32,
+        reg_addr: u8,
+        data: &mut [u8],
+    ) -> Result<(), MonitorError> {
+        self.i2c_transaction(gpu_index, reg_addr, data, I2cOp::Read)
+    }
+
+    /// Write data to a register on the IT8915 via I2C
+    ///
+    /// Useful for configuring the IT8915 before sampling, e.g. setting its
+    /// averaging/conversion-rate or range registers, rather than only
+    /// reading its fixed telemetry block.
+    ///
+    /// # Arguments
+    /// * `gpu_index` - GPU index (0-based)
+    /// * `reg_addr` - IT8915 register address
+    /// * `data` - Bytes to write
+    ///
+    /// # Returns
+    /// * `Ok(())` on success
+    /// * `Err(MonitorError)` describing why the transaction failed
+    #[allow(dead_code)]
+    pub fn write_i2c_data(
+        &self,
+        gpu_index: i32,
+        reg_addr: u8,
+        data: &[u8],
+    ) -> Result<(), MonitorError> {
+        let mut data = data.to_vec();
+        self.i2c_transaction(gpu_index, reg_addr, &mut data, I2cOp::Write)
     }
 
     /// Get power status for a specific GPU by reading IT8915 power monitoring IC
@@ -119,21 +350,13 @@ impl AstralPowerMonitor {
     ///
     /// # Returns
     /// * `Ok(())` on success
-    /// * `Err(String)` with error message on failure
+    /// * `Err(MonitorError)` describing why the read failed
     pub fn get_power_status(
         &self,
         gpu_index: i32,
         voltage_buffer: &mut [f32; 6],
         current_buffer: &mut [f32; 6],
-    ) -> Result<(), String> {
-        if gpu_index < 0 || gpu_index >= self.gpu_handles.len() as i32 {
-            return Err(format!(
-                "Invalid GPU index {}. Valid range: 0-{}",
-                gpu_index,
-                self.gpu_handles.len() - 1
-            ));
-        }
-
+    ) -> Result<(), MonitorError> {
         // Read 24 bytes from IT8915 starting at register 0x80
         let mut raw_data = [0u8; IT8915_POWER_DATA_SIZE];
         self.read_i2c_data(gpu_index, IT8915_POWER_REG_START, &mut raw_data)?;
@@ -185,12 +408,87 @@ impl AstralPowerMonitor {
 
     /// Get power status and return as vectors (voltages, currents)
     #[allow(dead_code)]
-    pub fn get_power_status_vec(&self, gpu_idx: i32) -> Result<(Vec<f32>, Vec<f32>), String> {
+    pub fn get_power_status_vec(&self, gpu_idx: i32) -> Result<(Vec<f32>, Vec<f32>), MonitorError> {
         let mut voltages = [0.0f32; 6];
         let mut currents = [0.0f32; 6];
         self.get_power_status(gpu_idx, &mut voltages, &mut currents)?;
         Ok((voltages.to_vec(), currents.to_vec()))
     }
+
+    /// Query NVAPI's own power and voltage telemetry for a GPU, independent
+    /// of the IT8915 I2C readout.
+    ///
+    /// # Arguments
+    /// * `gpu_index` - GPU index (0-based)
+    pub fn get_native_power(&self, gpu_index: i32) -> Result<NativePower, MonitorError> {
+        if gpu_index < 0 || gpu_index >= self.gpu_handles.len() as i32 {
+            return Err(MonitorError::InvalidGpuIndex {
+                index: gpu_index,
+                max: self.gpu_handles.len() - 1,
+            });
+        }
+
+        let gpu_handle = self.gpu_handles[gpu_index as usize];
+
+        unsafe {
+            let mut power_status: NV_GPU_POWER_STATUS = mem::zeroed();
+            power_status.version = NV_GPU_POWER_STATUS_VER;
+
+            let status = NvAPI_GPU_GetPowerStatus(gpu_handle, &mut power_status);
+            if status != NVAPI_OK {
+                return Err(MonitorError::Nvapi(format!(
+                    "Failed to query GPU power status: NVAPI status {:?}",
+                    status
+                )));
+            }
+
+            let mut voltage_status: NV_GPU_VOLTAGE_STATUS = mem::zeroed();
+            voltage_status.version = NV_GPU_VOLTAGE_STATUS_VER;
+
+            let status = NvAPI_GPU_GetVoltageStatus(gpu_handle, &mut voltage_status);
+            if status != NVAPI_OK {
+                return Err(MonitorError::Nvapi(format!(
+                    "Failed to query GPU voltage status: NVAPI status {:?}",
+                    status
+                )));
+            }
+
+            // Both values come back in milli-units, same convention as the
+            // IT8915 telemetry decoded in get_power_status above.
+            Ok(NativePower {
+                power_watts: power_status.entries[0].power as f32 * 0.001,
+                voltage_volts: voltage_status.value as f32 * 0.001,
+            })
+        }
+    }
+
+    /// Cross-validate the IT8915-derived per-pin power total against
+    /// NVAPI's own reported power for the same GPU.
+    ///
+    /// # Arguments
+    /// * `gpu_index` - GPU index (0-based)
+    pub fn get_validated_power_status(
+        &self,
+        gpu_index: i32,
+    ) -> Result<ValidatedPowerStatus, MonitorError> {
+        let mut voltages = [0.0f32; 6];
+        let mut currents = [0.0f32; 6];
+        self.get_power_status(gpu_index, &mut voltages, &mut currents)?;
+
+        let pin_power_watts: f32 = voltages
+            .iter()
+            .zip(currents.iter())
+            .map(|(v, i)| v * i)
+            .sum();
+
+        let native = self.get_native_power(gpu_index)?;
+
+        Ok(ValidatedPowerStatus {
+            pin_power_watts,
+            native,
+            delta_watts: pin_power_watts - native.power_watts,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -202,4 +500,53 @@ mod tests {
         let monitor = AstralPowerMonitor::new();
         assert!(monitor.is_ok() || monitor.is_err()); // Will fail on non-NVIDIA systems
     }
+
+    #[test]
+    fn bus_status_decodes_known_codes() {
+        assert_eq!(I2cBusStatus::from_raw(0), I2cBusStatus::Okay);
+        assert_eq!(I2cBusStatus::from_raw(1), I2cBusStatus::NoAck);
+        assert_eq!(I2cBusStatus::from_raw(2), I2cBusStatus::Timeout);
+        assert_eq!(I2cBusStatus::from_raw(3), I2cBusStatus::BusBusy);
+        assert_eq!(I2cBusStatus::from_raw(42), I2cBusStatus::Other(42));
+    }
+
+    #[test]
+    fn only_timeout_and_bus_busy_are_transient() {
+        assert!(!I2cBusStatus::Okay.is_transient());
+        assert!(!I2cBusStatus::NoAck.is_transient());
+        assert!(I2cBusStatus::Timeout.is_transient());
+        assert!(I2cBusStatus::BusBusy.is_transient());
+        assert!(!I2cBusStatus::Other(42).is_transient());
+    }
+
+    fn validated(pin_power_watts: f32, native_power_watts: f32) -> ValidatedPowerStatus {
+        ValidatedPowerStatus {
+            pin_power_watts,
+            native: NativePower {
+                power_watts: native_power_watts,
+                voltage_volts: 12.0,
+            },
+            delta_watts: pin_power_watts - native_power_watts,
+        }
+    }
+
+    #[test]
+    fn agreeing_readings_do_not_diverge() {
+        let status = validated(300.0, 295.0);
+        assert!(!status.diverges_beyond(15.0));
+    }
+
+    #[test]
+    fn miscalibrated_block_diverges_past_tolerance() {
+        let status = validated(300.0, 250.0);
+        assert!(status.diverges_beyond(15.0));
+    }
+
+    #[test]
+    fn divergence_check_is_symmetric_in_sign() {
+        let over = validated(300.0, 250.0);
+        let under = validated(250.0, 300.0);
+        assert!(over.diverges_beyond(15.0));
+        assert!(under.diverges_beyond(15.0));
+    }
 }
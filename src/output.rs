@@ -0,0 +1,409 @@
+//! Output formats for sampled snapshots.
+//!
+//! The live ANSI terminal view is one way to consume a `Snapshot`, but not
+//! the only one: a `Formatter` turns a snapshot into text, and a `Sink`
+//! writes that text somewhere (stdout or a file). Sampling never needs to
+//! know which combination is active, and a new format/destination can be
+//! added without touching `sampling.rs`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::time::UNIX_EPOCH;
+
+use crate::analysis::AlertThresholds;
+use crate::sampling::Snapshot;
+
+/// Turns one `Snapshot` into the text that should be written to a `Sink`.
+pub trait Formatter {
+    fn format(&mut self, snapshot: &Snapshot) -> String;
+}
+
+/// A destination for formatted output.
+pub trait Sink {
+    fn write(&mut self, text: &str);
+}
+
+/// Writes to stdout and flushes after every snapshot, so piping to another
+/// process sees data as it arrives rather than buffered.
+pub struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn write(&mut self, text: &str) {
+        print!("{}", text);
+        io::stdout().flush().unwrap();
+    }
+}
+
+/// Appends to a file on disk, creating it if it doesn't exist yet.
+pub struct FileSink {
+    file: File,
+}
+
+impl FileSink {
+    /// Open `path` for appending, creating it if needed. Returns whether the
+    /// file already had content, so a caller like `CsvFormatter` can avoid
+    /// writing a second header partway through an existing log.
+    pub fn create(path: &str) -> io::Result<(Self, bool)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let had_content = file.metadata()?.len() > 0;
+        Ok((Self { file }, had_content))
+    }
+}
+
+impl Sink for FileSink {
+    fn write(&mut self, text: &str) {
+        if let Err(e) = self.file.write_all(text.as_bytes()) {
+            eprintln!("Error writing to output file: {}", e);
+        }
+    }
+}
+
+fn timestamp_ms(snapshot: &Snapshot) -> u128 {
+    snapshot
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// The existing live ANSI terminal view, ported from the original in-place
+/// refresh but sized to the number of lines actually drawn so it works for
+/// any number of GPUs.
+pub struct PrettyFormatter {
+    thresholds: AlertThresholds,
+    power_tolerance_watts: f32,
+    previous_lines: usize,
+    first_tick: bool,
+}
+
+impl PrettyFormatter {
+    pub fn new(thresholds: AlertThresholds, power_tolerance_watts: f32) -> Self {
+        Self {
+            thresholds,
+            power_tolerance_watts,
+            previous_lines: 0,
+            first_tick: true,
+        }
+    }
+}
+
+impl Formatter for PrettyFormatter {
+    fn format(&mut self, snapshot: &Snapshot) -> String {
+        let mut out = String::new();
+
+        if self.first_tick {
+            self.first_tick = false;
+        } else {
+            // Move cursor up by exactly as many lines as the last tick drew,
+            // then clear everything below so a tick that draws fewer lines
+            // than the last one (e.g. a GPU dropped from the snapshot after
+            // its read failed) doesn't leave stale text on screen.
+            out.push_str(&format!("\x1b[{}A\x1b[J", self.previous_lines));
+        }
+
+        let mut lines = 0usize;
+
+        for gpu in &snapshot.gpus {
+            out.push_str(&format!("GPU {} Power Rail Status:\n", gpu.gpu_index));
+            out.push_str("==========================\n");
+            lines += 2;
+
+            let mut total_power = 0.0f32;
+            for i in 0..6 {
+                let power = gpu.voltages[i] * gpu.currents[i];
+                total_power += power;
+
+                let current_color = if gpu.analysis.pin_alerts[i] {
+                    "\x1b[91m" // Bright red: over the absolute limit or carrying a disproportionate share
+                } else if gpu.currents[i] >= self.thresholds.max_current_amps * (2.0 / 3.0) {
+                    "\x1b[93m" // Bright yellow: approaching the limit
+                } else {
+                    "\x1b[92m" // Bright green
+                };
+
+                out.push_str(&format!(
+                    "  Pin {}: {:.3}V Ã— {}{:.2}A{} = {:.2}W\x1b[K\n",
+                    i + 1,
+                    gpu.voltages[i],
+                    current_color,
+                    gpu.currents[i],
+                    "\x1b[0m",
+                    power
+                ));
+                lines += 1;
+            }
+
+            out.push_str("\x1b[K\n"); // Clear blank line
+            lines += 1;
+            out.push_str(&format!("  Total Power: {:.2}W\x1b[K\n", total_power));
+            lines += 1;
+
+            if gpu.analysis.imbalance_alert {
+                out.push_str(&format!(
+                    "  \x1b[91mImbalance: Pin {} is {:.2}A above the {:.2}A mean\x1b[0m\x1b[K\n",
+                    gpu.analysis.max_deviation_pin + 1,
+                    gpu.analysis.max_deviation_amps,
+                    gpu.analysis.mean_current_amps
+                ));
+            } else {
+                out.push_str("  Imbalance: none\x1b[K\n");
+            }
+            lines += 1;
+
+            match gpu.validated_power {
+                Some(validated) if validated.diverges_beyond(self.power_tolerance_watts) => {
+                    out.push_str(&format!(
+                        "  \x1b[91mPower check: IT8915 {:.2}W vs NVAPI {:.2}W (Δ{:.2}W)\x1b[0m\x1b[K\n",
+                        validated.pin_power_watts, validated.native.power_watts, validated.delta_watts
+                    ));
+                }
+                Some(validated) => {
+                    out.push_str(&format!(
+                        "  Power check: IT8915 {:.2}W vs NVAPI {:.2}W (Δ{:.2}W)\x1b[K\n",
+                        validated.pin_power_watts, validated.native.power_watts, validated.delta_watts
+                    ));
+                }
+                None => out.push_str("  Power check: unavailable\x1b[K\n"),
+            }
+            lines += 1;
+
+            out.push_str("\x1b[K\n"); // Blank line separating GPU blocks
+            lines += 1;
+        }
+
+        self.previous_lines = lines;
+        out
+    }
+}
+
+/// Newline-delimited JSON: one object per GPU per tick.
+pub struct JsonLinesFormatter {
+    power_tolerance_watts: f32,
+}
+
+impl JsonLinesFormatter {
+    pub fn new(power_tolerance_watts: f32) -> Self {
+        Self {
+            power_tolerance_watts,
+        }
+    }
+}
+
+impl Formatter for JsonLinesFormatter {
+    fn format(&mut self, snapshot: &Snapshot) -> String {
+        let ts = timestamp_ms(snapshot);
+        let mut out = String::new();
+
+        for gpu in &snapshot.gpus {
+            let powers: Vec<f32> = (0..6).map(|i| gpu.voltages[i] * gpu.currents[i]).collect();
+            let total_power: f32 = powers.iter().sum();
+
+            let native_power_json = match gpu.validated_power {
+                Some(v) => format!(
+                    "{{\"power_watts\":{:.3},\"voltage_volts\":{:.3},\"delta_watts\":{:.3},\"diverges\":{}}}",
+                    v.native.power_watts,
+                    v.native.voltage_volts,
+                    v.delta_watts,
+                    v.diverges_beyond(self.power_tolerance_watts)
+                ),
+                None => "null".to_string(),
+            };
+
+            out.push_str(&format!(
+                "{{\"timestamp_ms\":{},\"gpu_index\":{},\"voltages\":{},\"currents\":{},\"powers\":{},\
+                 \"total_power_watts\":{:.3},\"mean_current_amps\":{:.3},\"max_deviation_amps\":{:.3},\
+                 \"max_deviation_pin\":{},\"imbalance_alert\":{},\"native_power\":{}}}\n",
+                ts,
+                gpu.gpu_index,
+                format_f32_array(&gpu.voltages),
+                format_f32_array(&gpu.currents),
+                format_f32_array(&powers),
+                total_power,
+                gpu.analysis.mean_current_amps,
+                gpu.analysis.max_deviation_amps,
+                gpu.analysis.max_deviation_pin,
+                gpu.analysis.imbalance_alert,
+                native_power_json,
+            ));
+        }
+
+        out
+    }
+}
+
+fn format_f32_array(values: &[f32]) -> String {
+    let joined = values
+        .iter()
+        .map(|v| format!("{:.3}", v))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("[{}]", joined)
+}
+
+/// CSV: one row per GPU per tick, with a header written once.
+pub struct CsvFormatter {
+    header_written: bool,
+    power_tolerance_watts: f32,
+}
+
+impl CsvFormatter {
+    /// `skip_header` should be `true` when appending to a file that already
+    /// has a header row (e.g. the monitor was restarted against an existing
+    /// `--output` log), so a second header doesn't get written partway
+    /// through the file.
+    pub fn new(skip_header: bool, power_tolerance_watts: f32) -> Self {
+        Self {
+            header_written: skip_header,
+            power_tolerance_watts,
+        }
+    }
+}
+
+impl Formatter for CsvFormatter {
+    fn format(&mut self, snapshot: &Snapshot) -> String {
+        let mut out = String::new();
+
+        if !self.header_written {
+            out.push_str("timestamp_ms,gpu_index,");
+            for i in 1..=6 {
+                out.push_str(&format!("voltage_{}_v,current_{}_a,power_{}_w,", i, i, i));
+            }
+            out.push_str(
+                "total_power_w,mean_current_a,max_deviation_a,max_deviation_pin,imbalance_alert,\
+                 native_power_w,native_voltage_v,power_delta_w,power_diverges\n",
+            );
+            self.header_written = true;
+        }
+
+        let ts = timestamp_ms(snapshot);
+
+        for gpu in &snapshot.gpus {
+            out.push_str(&format!("{},{},", ts, gpu.gpu_index));
+
+            let mut total_power = 0.0f32;
+            for i in 0..6 {
+                let power = gpu.voltages[i] * gpu.currents[i];
+                total_power += power;
+                out.push_str(&format!("{:.3},{:.3},{:.3},", gpu.voltages[i], gpu.currents[i], power));
+            }
+
+            out.push_str(&format!(
+                "{:.3},{:.3},{:.3},{},{},",
+                total_power,
+                gpu.analysis.mean_current_amps,
+                gpu.analysis.max_deviation_amps,
+                gpu.analysis.max_deviation_pin,
+                gpu.analysis.imbalance_alert,
+            ));
+
+            match gpu.validated_power {
+                Some(v) => out.push_str(&format!(
+                    "{:.3},{:.3},{:.3},{}\n",
+                    v.native.power_watts,
+                    v.native.voltage_volts,
+                    v.delta_watts,
+                    v.diverges_beyond(self.power_tolerance_watts)
+                )),
+                None => out.push_str(",,,\n"),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::PowerAnalysis;
+    use crate::monitor::{NativePower, ValidatedPowerStatus};
+    use crate::sampling::GpuSample;
+    use std::time::SystemTime;
+
+    fn sample_snapshot(validated_power: Option<ValidatedPowerStatus>) -> Snapshot {
+        let voltages = [12.0; 6];
+        let currents = [5.0; 6];
+        let analysis = PowerAnalysis::compute(&currents, &AlertThresholds::default());
+
+        Snapshot {
+            timestamp: SystemTime::now(),
+            gpus: vec![GpuSample {
+                gpu_index: 0,
+                voltages,
+                currents,
+                analysis,
+                validated_power,
+            }],
+            failures: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn json_lines_formatter_emits_one_object_per_gpu() {
+        let snapshot = sample_snapshot(None);
+        let mut formatter = JsonLinesFormatter::new(15.0);
+
+        let text = formatter.format(&snapshot);
+
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.contains("\"gpu_index\":0"));
+        assert!(text.contains("\"native_power\":null"));
+    }
+
+    #[test]
+    fn json_lines_formatter_reports_native_power_divergence() {
+        let validated = ValidatedPowerStatus {
+            pin_power_watts: 360.0,
+            native: NativePower {
+                power_watts: 300.0,
+                voltage_volts: 12.0,
+            },
+            delta_watts: 60.0,
+        };
+        let snapshot = sample_snapshot(Some(validated));
+        let mut formatter = JsonLinesFormatter::new(15.0);
+
+        let text = formatter.format(&snapshot);
+
+        assert!(text.contains("\"diverges\":true"));
+    }
+
+    #[test]
+    fn csv_formatter_writes_header_once() {
+        let snapshot = sample_snapshot(None);
+        let mut formatter = CsvFormatter::new(false, 15.0);
+
+        let first = formatter.format(&snapshot);
+        let second = formatter.format(&snapshot);
+
+        assert!(first.starts_with("timestamp_ms,gpu_index,"));
+        assert!(!second.starts_with("timestamp_ms,gpu_index,"));
+    }
+
+    #[test]
+    fn csv_formatter_skips_header_when_file_already_has_one() {
+        let snapshot = sample_snapshot(None);
+        let mut formatter = CsvFormatter::new(true, 15.0);
+
+        let text = formatter.format(&snapshot);
+
+        assert!(!text.contains("timestamp_ms"));
+    }
+
+    #[test]
+    fn pretty_formatter_tracks_lines_drawn_across_ticks() {
+        let snapshot = sample_snapshot(None);
+        let mut formatter = PrettyFormatter::new(AlertThresholds::default(), 15.0);
+
+        let first = formatter.format(&snapshot);
+        let second = formatter.format(&snapshot);
+
+        // First tick draws straight through, no cursor movement yet.
+        assert!(!first.starts_with("\x1b["));
+        // Second tick rewinds the cursor and clears stale lines below.
+        assert!(second.starts_with(&format!("\x1b[{}A\x1b[J", formatter.previous_lines)));
+    }
+}
@@ -0,0 +1,74 @@
+//! Sampling of every enumerated GPU, decoupled from how the result gets
+//! displayed. A `Snapshot` is a self-contained sweep across all GPUs at one
+//! point in time; renderers (ANSI terminal, JSON, CSV, ...) consume it
+//! without needing to know anything about NVAPI.
+
+use std::time::SystemTime;
+
+use crate::analysis::{AlertThresholds, PowerAnalysis};
+use crate::monitor::{AstralPowerMonitor, MonitorError, ValidatedPowerStatus};
+
+/// One GPU's readings for a single sampling tick.
+#[derive(Debug, Clone)]
+pub struct GpuSample {
+    pub gpu_index: usize,
+    pub voltages: [f32; 6],
+    pub currents: [f32; 6],
+    pub analysis: PowerAnalysis,
+    /// Cross-check against NVAPI's own power/voltage telemetry. `None` if
+    /// the native query failed (e.g. unsupported on this GPU) — that's not
+    /// fatal to the sample, since the IT8915 reading above is still valid.
+    pub validated_power: Option<ValidatedPowerStatus>,
+}
+
+/// A full sweep across every enumerated GPU at one point in time.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub timestamp: SystemTime,
+    pub gpus: Vec<GpuSample>,
+    /// GPUs whose read failed this tick, alongside why, so a caller can
+    /// tell a permanent condition (`MonitorError::NoAck`) apart from a
+    /// transient one (`MonitorError::RetriesExhausted(BusBusy)`) instead of
+    /// the failure being silently swallowed.
+    pub failures: Vec<(usize, MonitorError)>,
+}
+
+/// Poll every GPU returned by `monitor.gpu_count()` once and collect the
+/// results into a single timestamped snapshot. A GPU whose read fails this
+/// tick is omitted from `Snapshot::gpus`, but its error is preserved in
+/// `Snapshot::failures` rather than discarded.
+pub fn sample_all(monitor: &AstralPowerMonitor, thresholds: &AlertThresholds) -> Snapshot {
+    let mut gpus = Vec::with_capacity(monitor.gpu_count());
+    let mut failures = Vec::new();
+
+    for gpu_index in 0..monitor.gpu_count() {
+        let mut voltages = [0.0f32; 6];
+        let mut currents = [0.0f32; 6];
+
+        match monitor.get_power_status(gpu_index as i32, &mut voltages, &mut currents) {
+            Ok(()) => {
+                let analysis = PowerAnalysis::compute(&currents, thresholds);
+
+                // The native power cross-check is not a requirement: a GPU
+                // that doesn't support it still yields a valid
+                // IT8915-derived sample, just without validation.
+                let validated_power = monitor.get_validated_power_status(gpu_index as i32).ok();
+
+                gpus.push(GpuSample {
+                    gpu_index,
+                    voltages,
+                    currents,
+                    analysis,
+                    validated_power,
+                });
+            }
+            Err(e) => failures.push((gpu_index, e)),
+        }
+    }
+
+    Snapshot {
+        timestamp: SystemTime::now(),
+        gpus,
+        failures,
+    }
+}